@@ -1,10 +1,15 @@
+use std::collections::HashMap;
+use std::convert::Infallible;
 use std::{env, process};
 use std::fs;
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-use gpio_cdev::{Chip, LineHandle, LineRequestFlags};
+use futures::stream::{self, StreamExt};
+use gpio_cdev::{AsyncLineEventHandle, Chip, EventRequestFlags, EventType, LineRequestFlags, MultiLineHandle};
 use gpio_cdev::errors::Error;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
 use warp::Filter;
 
 #[derive(Deserialize)]
@@ -19,11 +24,22 @@ struct Main {
     port: u16,
 }
 
+// No `bias` field: gpio_cdev only implements the kernel's GPIOHANDLE v1 ABI, which
+// has no bias bits at all (pull-up/pull-down/disabled is a GPIO-v2/libgpiod concept).
+// Pin bias can't be configured through this dependency.
 #[derive(Clone, Deserialize)]
 struct Gpio {
     chip: String,
     pins: Vec<u32>,
     names: Vec<String>,
+    #[serde(default)]
+    directions: Vec<String>,
+    #[serde(default)]
+    active_low: Vec<bool>,
+    #[serde(default)]
+    drive: Vec<String>,
+    #[serde(default)]
+    values: Vec<u8>,
 }
 
 #[derive(Deserialize)]
@@ -38,24 +54,181 @@ struct FormDataName {
     state: u8,
 }
 
-struct HandlePair {
-    handle: LineHandle,
+#[derive(Deserialize)]
+struct BatchItem {
+    #[serde(default)]
+    pin: Option<usize>,
+    #[serde(default)]
+    name: Option<String>,
+    state: u8,
+}
+
+#[derive(Deserialize)]
+struct PulseItem {
+    #[serde(default)]
+    pin: Option<usize>,
+    #[serde(default)]
+    name: Option<String>,
+    state: u8,
+    duration_ms: u64,
+}
+
+#[derive(Serialize)]
+struct PulseResponse {
+    pin: usize,
+    state: u8,
+    revert_at_ms: u128,
+}
+
+#[derive(Serialize)]
+struct PulseError {
+    error: &'static str,
+}
+
+// A single multi-line kernel request takes one set of flags for every offset in it,
+// so output lines are grouped by their (active-low/drive) flags into one
+// OutputBank per distinct group rather than one bank for all outputs. A set/batch
+// touching lines in more than one group is therefore atomic per-group, not globally.
+struct OutputBank {
+    handle: MultiLineHandle,
+    offsets: Vec<u32>,
+}
+
+// Maps a configured output line (`offset` is its chip line offset) to where it lives:
+// which bank holds it, and its position (`local`) within that bank's handle. Kept in
+// the original config order so /get/{id}
+// and `pin` fields in request bodies keep indexing outputs the same way they always
+// have, regardless of how outputs got grouped into banks.
+struct OutputEntry {
+    name: String,
+    offset: u32,
+    bank: usize,
+    local: usize,
+}
+
+// One broadcast channel per input line, fed by a single background task reading the
+// AsyncLineEventHandle. Any number of /events/{name} clients can subscribe and
+// reconnect freely; the line is only ever requested from the kernel once.
+// `last_value` mirrors the line's level as of the most recent edge (seeded from an
+// initial read) so routes like /info can report a live value without needing their
+// own handle on a line whose only handle lives inside the broadcaster task.
+struct EventSource {
+    name: String,
+    sender: broadcast::Sender<EdgeEvent>,
+    last_value: Arc<Mutex<u8>>,
+}
+
+struct LineMeta {
+    pin: u32,
+    name: String,
+    direction: String,
+}
+
+#[derive(Serialize)]
+struct LineInfoResponse {
+    chip: String,
+    label: String,
+    offset: u32,
+    name: String,
+    consumer: String,
+    direction: String,
+    value: String,
+}
+
+#[derive(Clone, Serialize)]
+struct EdgeEvent {
     name: String,
+    edge: &'static str,
+    timestamp_ns: u64,
+}
+
+fn line_flags(base: LineRequestFlags, active_low: bool, drive: &str) -> LineRequestFlags {
+    let mut flags = base;
+    if active_low {
+        flags |= LineRequestFlags::ACTIVE_LOW;
+    }
+    match drive {
+        "open-drain" => flags |= LineRequestFlags::OPEN_DRAIN,
+        "open-source" => flags |= LineRequestFlags::OPEN_SOURCE,
+        _ => {}
+    }
+    flags
+}
+
+fn get_bank_out(chip: &mut Chip, pins: &[u32], flags: LineRequestFlags, values: &[u8]) -> Result<MultiLineHandle, Error> {
+    chip.get_lines(pins)?.request(flags, values, "gpio-api")
 }
 
-fn get_handle_out(chip: &mut Chip, pin: u32) -> Result<LineHandle, Error> {
+fn get_handle_events(chip: &mut Chip, pin: u32, flags: LineRequestFlags) -> Result<(AsyncLineEventHandle, u8), Error> {
     let handle = chip
         .get_line(pin)?
-        .request(LineRequestFlags::OUTPUT, 0, "gpio-api")?;
-    Ok(handle)
+        .events(flags, EventRequestFlags::BOTH_EDGES, "gpio-api")?;
+    let initial = handle.get_value()?;
+    let events = AsyncLineEventHandle::new(handle)?;
+    Ok((events, initial))
+}
+
+// Reads the line in a background task and republishes each edge on a broadcast
+// channel; an I/O error from the kernel ends the task gracefully instead of
+// panicking the whole process, and the channel stays around for future subscribers.
+// `last_value` is updated alongside each edge so routes that don't own the handle
+// can still read the line's current level.
+fn spawn_event_broadcaster(
+    name: String,
+    mut handle: AsyncLineEventHandle,
+    initial: u8,
+    debug: bool,
+) -> (broadcast::Sender<EdgeEvent>, Arc<Mutex<u8>>) {
+    let (tx, _rx) = broadcast::channel(16);
+    let sender = tx.clone();
+    let last_value = Arc::new(Mutex::new(initial));
+    let last_value_task = last_value.clone();
+    tokio::spawn(async move {
+        while let Some(event) = handle.next().await {
+            match event {
+                Ok(event) => {
+                    let (edge, value) = match event.event_type() {
+                        EventType::RisingEdge => ("rising", 1u8),
+                        EventType::FallingEdge => ("falling", 0u8),
+                    };
+                    *last_value_task.lock().unwrap() = value;
+                    let _ = sender.send(EdgeEvent {
+                        name: name.clone(),
+                        edge,
+                        timestamp_ns: event.timestamp(),
+                    });
+                }
+                Err(err) => {
+                    if debug { println!("error reading GPIO events for {}: {}", name, err) }
+                    break;
+                }
+            }
+        }
+    });
+    (tx, last_value)
+}
+
+fn event_stream(rx: broadcast::Receiver<EdgeEvent>) -> impl futures::Stream<Item = Result<warp::sse::Event, Infallible>> {
+    stream::unfold(rx, |mut rx| async move {
+        loop {
+            match rx.recv().await {
+                Ok(event) => {
+                    let msg = warp::sse::Event::default().json_data(event).unwrap();
+                    return Some((Ok(msg), rx));
+                }
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    })
 }
 
-fn get_handle_value(handle: &LineHandle) -> &'static str {
-    match handle.get_value() {
-        Ok(v) => {
-            match v {
-                0 => "0",
-                1 => "1",
+fn get_bank_value(bank: &OutputBank, idx: usize) -> &'static str {
+    match bank.handle.get_values() {
+        Ok(values) => {
+            match values.get(idx) {
+                Some(0) => "0",
+                Some(1) => "1",
                 _ => "err",
             }
         }
@@ -63,10 +236,21 @@ fn get_handle_value(handle: &LineHandle) -> &'static str {
     }
 }
 
-fn set_handle_value(handle: &LineHandle, value: u8, debug: bool) {
-    match handle.set_value(value) {
+fn set_bank_value(bank: &OutputBank, idx: usize, value: u8, debug: bool) {
+    let mut values = match bank.handle.get_values() {
+        Ok(values) => values,
+        Err(err) => {
+            if debug { println!("error: {}", err) }
+            return;
+        }
+    };
+    if idx >= values.len() {
+        return;
+    }
+    values[idx] = value;
+    match bank.handle.set_values(&values) {
         Ok(()) => {
-            if debug { println!("pin {} set to {}", handle.line().offset(), value) }
+            if debug { println!("pin {} set to {}", bank.offsets[idx], value) }
         }
         Err(err) => {
             if debug { println!("error: {}", err) }
@@ -74,6 +258,56 @@ fn set_handle_value(handle: &LineHandle, value: u8, debug: bool) {
     }
 }
 
+fn now_ms() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_millis()
+}
+
+fn resolve_output_index(outputs: &[OutputEntry], pin: Option<usize>, name: Option<&str>) -> Option<usize> {
+    match (pin, name) {
+        (Some(pin), _) if pin < outputs.len() => Some(pin),
+        (None, Some(name)) => outputs.iter().position(|entry| entry.name == name),
+        _ => None,
+    }
+}
+
+fn set_bank_values(bank: &OutputBank, updates: &[(usize, u8)], debug: bool) -> Result<(), String> {
+    let mut values = bank.handle.get_values().map_err(|err| err.to_string())?;
+    for (idx, value) in updates {
+        if *idx >= values.len() {
+            return Err(format!("invalid GPIO index {}", idx));
+        }
+        values[*idx] = *value;
+    }
+    bank.handle.set_values(&values).map_err(|err| err.to_string())?;
+    if debug {
+        println!("batch set {} pins", updates.len())
+    }
+    Ok(())
+}
+
+// Updates may span several banks when the targeted outputs were configured with
+// different active-low/drive flags; each bank is still set atomically, but
+// the batch as a whole is only atomic within a bank, not across banks.
+fn set_output_values(
+    banks: &[OutputBank],
+    outputs: &[OutputEntry],
+    updates: &[(usize, u8)],
+    debug: bool,
+) -> Result<(), String> {
+    let mut by_bank: HashMap<usize, Vec<(usize, u8)>> = HashMap::new();
+    for (idx, value) in updates {
+        let entry = &outputs[*idx];
+        by_bank.entry(entry.bank).or_insert_with(Vec::new).push((entry.local, *value));
+    }
+    for (bank_idx, local_updates) in by_bank {
+        set_bank_values(&banks[bank_idx], &local_updates, debug)?;
+    }
+    Ok(())
+}
+
 #[tokio::main]
 async fn main() {
     let config_path = env::args().nth(1).unwrap_or_else(|| {
@@ -94,26 +328,120 @@ async fn main() {
         process::exit(1);
     });
 
-    let handles: Arc<Mutex<Vec<HandlePair>>> = Arc::new(Mutex::new(Vec::new()));
-    for (pin, name) in config.gpio.pins.iter().zip(config.gpio.names) {
-        let pin = get_handle_out(&mut chip, *pin).unwrap_or_else(|err| {
-            println!("error opening GPIO pin {}: {}", pin, err);
+    let directions = if config.gpio.directions.is_empty() {
+        vec!["out".to_string(); config.gpio.pins.len()]
+    } else {
+        config.gpio.directions.clone()
+    };
+
+    struct PendingOutput {
+        pin: u32,
+        name: String,
+        flags: LineRequestFlags,
+        value: u8,
+    }
+
+    let mut pending_outputs: Vec<PendingOutput> = Vec::new();
+    let mut line_meta: Vec<LineMeta> = Vec::new();
+    let mut event_sources: Vec<EventSource> = Vec::new();
+
+    for (i, (pin, name)) in config.gpio.pins.iter().zip(config.gpio.names.iter()).enumerate() {
+        let direction = directions.get(i).map(String::as_str).unwrap_or("out");
+        let active_low = config.gpio.active_low.get(i).copied().unwrap_or(false);
+        let drive = config.gpio.drive.get(i).map(String::as_str).unwrap_or("");
+
+        line_meta.push(LineMeta { pin: *pin, name: name.clone(), direction: direction.to_string() });
+
+        if direction == "in" {
+            let flags = line_flags(LineRequestFlags::INPUT, active_low, drive);
+            let (events, initial) = get_handle_events(&mut chip, *pin, flags).unwrap_or_else(|err| {
+                println!("error opening GPIO pin {}: {}", pin, err);
+                process::exit(1);
+            });
+            let (sender, last_value) = spawn_event_broadcaster(name.clone(), events, initial, config.main.debug);
+            event_sources.push(EventSource { name: name.clone(), sender, last_value });
+        } else {
+            let flags = line_flags(LineRequestFlags::OUTPUT, active_low, drive);
+            let value = config.gpio.values.get(i).copied().unwrap_or(0);
+            pending_outputs.push(PendingOutput { pin: *pin, name: name.clone(), flags, value });
+        }
+    }
+
+    // Outputs that share the same flags are requested from the kernel as one
+    // MultiLineHandle; outputs with different active-low/drive settings land
+    // in separate banks instead of silently losing their configured flags.
+    let mut bank_bits: Vec<u32> = Vec::new();
+    let mut bank_pins: Vec<Vec<u32>> = Vec::new();
+    let mut bank_values: Vec<Vec<u8>> = Vec::new();
+    let mut bank_index_of: HashMap<u32, usize> = HashMap::new();
+    let mut local_index: Vec<usize> = Vec::new();
+
+    for out in &pending_outputs {
+        let bits = out.flags.bits();
+        let bank_idx = *bank_index_of.entry(bits).or_insert_with(|| {
+            bank_bits.push(bits);
+            bank_pins.push(Vec::new());
+            bank_values.push(Vec::new());
+            bank_bits.len() - 1
+        });
+        local_index.push(bank_pins[bank_idx].len());
+        bank_pins[bank_idx].push(out.pin);
+        bank_values[bank_idx].push(out.value);
+    }
+
+    let mut banks: Vec<OutputBank> = Vec::new();
+    for (i, bits) in bank_bits.iter().enumerate() {
+        let flags = LineRequestFlags::from_bits_truncate(*bits);
+        let handle = get_bank_out(&mut chip, &bank_pins[i], flags, &bank_values[i]).unwrap_or_else(|err| {
+            println!("error opening GPIO outputs: {}", err);
             process::exit(1);
         });
-        handles.lock().unwrap().push(HandlePair {handle: pin, name: name.clone()});
+        banks.push(OutputBank { handle, offsets: bank_pins[i].clone() });
     }
-    let handles_filter = warp::any().map(move || handles.clone());
+
+    let outputs: Vec<OutputEntry> = pending_outputs
+        .iter()
+        .zip(local_index.iter())
+        .map(|(out, &local)| OutputEntry {
+            name: out.name.clone(),
+            offset: out.pin,
+            bank: bank_index_of[&out.flags.bits()],
+            local,
+        })
+        .collect();
+
+    let banks: Arc<Mutex<Vec<OutputBank>>> = Arc::new(Mutex::new(banks));
+    let banks_filter = warp::any().map(move || banks.clone());
+    let outputs = Arc::new(outputs);
+    let outputs_filter = warp::any().map(move || outputs.clone());
+    let event_sources = Arc::new(event_sources);
+    let event_sources_filter = warp::any().map(move || event_sources.clone());
+
+    let chip = Arc::new(Mutex::new(chip));
+    let chip_filter = warp::any().map(move || chip.clone());
+    let line_meta = Arc::new(line_meta);
+    let line_meta_filter = warp::any().map(move || line_meta.clone());
+
+    let pulses: Arc<Mutex<HashMap<usize, tokio::task::JoinHandle<()>>>> = Arc::new(Mutex::new(HashMap::new()));
+    let pulses_filter = warp::any().map(move || pulses.clone());
+
+    // The value to revert to once a pulse chain ends. Populated only when a chain
+    // starts (no pulse already in flight for that line) and reused, not re-read,
+    // each time a later request supersedes one still in flight — otherwise a
+    // superseding pulse would capture the still-pulsed value as its own "revert".
+    let baselines: Arc<Mutex<HashMap<usize, u8>>> = Arc::new(Mutex::new(HashMap::new()));
+    let baselines_filter = warp::any().map(move || baselines.clone());
 
     let debug = warp::any().map(move || config.main.debug.clone());
 
     let get = warp::path("get")
         .and(warp::path::param::<usize>())
-        .and(handles_filter.clone())
-        .map(|id: usize, pins: Arc<Mutex<Vec<HandlePair>>>| {
-            if let Some(pin) = pins.lock().unwrap().get(id) {
-                get_handle_value(&pin.handle)
-            } else {
-                "invalid GPIO"
+        .and(banks_filter.clone())
+        .and(outputs_filter.clone())
+        .map(|id: usize, banks: Arc<Mutex<Vec<OutputBank>>>, outputs: Arc<Vec<OutputEntry>>| {
+            match outputs.get(id) {
+                Some(entry) => get_bank_value(&banks.lock().unwrap()[entry.bank], entry.local),
+                None => "invalid GPIO",
             }
         });
 
@@ -121,32 +449,28 @@ async fn main() {
         .and(warp::path("set"))
         .and(warp::body::content_length_limit(1024 * 16))
         .and(warp::body::form())
-        .and(handles_filter.clone())
+        .and(banks_filter.clone())
+        .and(outputs_filter.clone())
         .and(debug.clone())
-        .map(|form: FormData, pins: Arc<Mutex<Vec<HandlePair>>>, debug: bool| {
-            if let Some(pin) = pins.lock().unwrap().get(form.pin as usize) {
-                set_handle_value(&pin.handle, form.state, debug);
-                "OK"
-            } else {
-                "invalid GPIO pin"
+        .map(|form: FormData, banks: Arc<Mutex<Vec<OutputBank>>>, outputs: Arc<Vec<OutputEntry>>, debug: bool| {
+            match outputs.get(form.pin as usize) {
+                Some(entry) => {
+                    set_bank_value(&banks.lock().unwrap()[entry.bank], entry.local, form.state, debug);
+                    "OK"
+                }
+                None => "invalid GPIO pin",
             }
         });
 
     let name_get = warp::path("name")
         .and(warp::path("get"))
         .and(warp::path::param::<String>())
-        .and(handles_filter.clone())
-        .map(|name: String, pins: Arc<Mutex<Vec<HandlePair>>>| {
-            let mut value = None;
-            for pin in pins.lock().unwrap().iter() {
-                if pin.name == name {
-                    value = Some(get_handle_value(&pin.handle));
-                }
-            }
-            if value.is_some() {
-                value.unwrap()
-            } else {
-                "invalid GPIO name"
+        .and(banks_filter.clone())
+        .and(outputs_filter.clone())
+        .map(|name: String, banks: Arc<Mutex<Vec<OutputBank>>>, outputs: Arc<Vec<OutputEntry>>| {
+            match outputs.iter().position(|entry| entry.name == name) {
+                Some(idx) => get_bank_value(&banks.lock().unwrap()[outputs[idx].bank], outputs[idx].local),
+                None => "invalid GPIO name",
             }
         });
 
@@ -155,43 +479,182 @@ async fn main() {
         .and(warp::path("set"))
         .and(warp::body::content_length_limit(1024 * 16))
         .and(warp::body::form())
-        .and(handles_filter.clone())
+        .and(banks_filter.clone())
+        .and(outputs_filter.clone())
+        .and(debug.clone())
+        .map(|form: FormDataName, banks: Arc<Mutex<Vec<OutputBank>>>, outputs: Arc<Vec<OutputEntry>>, debug: bool| {
+            match outputs.iter().position(|entry| entry.name == form.name) {
+                Some(idx) => {
+                    let entry = &outputs[idx];
+                    set_bank_value(&banks.lock().unwrap()[entry.bank], entry.local, form.state, debug);
+                    "OK"
+                }
+                None => "invalid GPIO name",
+            }
+        });
+
+    let set_batch = warp::post()
+        .and(warp::path("set"))
+        .and(warp::path("batch"))
+        .and(warp::body::content_length_limit(1024 * 16))
+        .and(warp::body::json())
+        .and(banks_filter.clone())
+        .and(outputs_filter.clone())
         .and(debug.clone())
-        .map(|form: FormDataName, pins: Arc<Mutex<Vec<HandlePair>>>, debug: bool| {
-            let mut name_match = false;
-            for pin in pins.lock().unwrap().iter() {
-                if pin.name == form.name {
-                    name_match = true;
-                    set_handle_value(&pin.handle, form.state, debug);
+        .map(|items: Vec<BatchItem>, banks: Arc<Mutex<Vec<OutputBank>>>, outputs: Arc<Vec<OutputEntry>>, debug: bool| {
+            let mut updates = Vec::with_capacity(items.len());
+            for item in items {
+                match resolve_output_index(&outputs, item.pin, item.name.as_deref()) {
+                    Some(idx) => updates.push((idx, item.state)),
+                    None => return "invalid GPIO in batch".to_string(),
                 }
             }
-            if name_match {
-                "OK"
-            } else {
-                "invalid GPIO name"
+            match set_output_values(&banks.lock().unwrap(), &outputs, &updates, debug) {
+                Ok(()) => "OK".to_string(),
+                Err(err) => err,
             }
         });
 
     let gpio = warp::path("gpio")
-        .and(handles_filter.clone())
-        .map(|pins: Arc<Mutex<Vec<HandlePair>>>| {
+        .and(banks_filter.clone())
+        .and(outputs_filter.clone())
+        .map(|banks: Arc<Mutex<Vec<OutputBank>>>, outputs: Arc<Vec<OutputEntry>>| {
             let mut out = String::new();
-            for pin in pins.lock().unwrap().iter() {
+            let banks = banks.lock().unwrap();
+            for entry in outputs.iter() {
                 out.push_str(&format!(
                     "pin: {} | name: {} | state: {}\n",
-                    pin.handle.line().offset(),
-                    pin.name,
-                    get_handle_value(&pin.handle),
+                    entry.offset,
+                    entry.name,
+                    get_bank_value(&banks[entry.bank], entry.local),
                 ));
             }
             out
         });
 
+    let info = warp::path("info")
+        .and(chip_filter.clone())
+        .and(banks_filter.clone())
+        .and(outputs_filter.clone())
+        .and(event_sources_filter.clone())
+        .and(line_meta_filter.clone())
+        .map(|chip: Arc<Mutex<Chip>>, banks: Arc<Mutex<Vec<OutputBank>>>, outputs: Arc<Vec<OutputEntry>>, event_sources: Arc<Vec<EventSource>>, metas: Arc<Vec<LineMeta>>| {
+            let mut chip = chip.lock().unwrap();
+            let banks = banks.lock().unwrap();
+            let mut out = Vec::with_capacity(metas.len());
+            for meta in metas.iter() {
+                let value = if meta.direction == "in" {
+                    event_sources
+                        .iter()
+                        .find(|source| source.name == meta.name)
+                        .map(|source| if *source.last_value.lock().unwrap() == 1 { "1" } else { "0" })
+                        .unwrap_or("err")
+                        .to_string()
+                } else {
+                    outputs
+                        .iter()
+                        .find(|entry| entry.name == meta.name)
+                        .map(|entry| get_bank_value(&banks[entry.bank], entry.local))
+                        .unwrap_or("err")
+                        .to_string()
+                };
+                let consumer = chip
+                    .get_line(meta.pin)
+                    .and_then(|line| line.info())
+                    .map(|info| info.consumer().unwrap_or("").to_string())
+                    .unwrap_or_default();
+                out.push(LineInfoResponse {
+                    chip: chip.name().to_string(),
+                    label: chip.label().to_string(),
+                    offset: meta.pin,
+                    name: meta.name.clone(),
+                    consumer,
+                    direction: meta.direction.clone(),
+                    value,
+                });
+            }
+            warp::reply::json(&out)
+        });
+
+    let pulse = warp::post()
+        .and(warp::path("pulse"))
+        .and(warp::body::content_length_limit(1024 * 16))
+        .and(warp::body::json())
+        .and(banks_filter.clone())
+        .and(outputs_filter.clone())
+        .and(pulses_filter.clone())
+        .and(baselines_filter.clone())
+        .and(debug.clone())
+        .map(|item: PulseItem, banks: Arc<Mutex<Vec<OutputBank>>>, outputs: Arc<Vec<OutputEntry>>, pulses: Arc<Mutex<HashMap<usize, tokio::task::JoinHandle<()>>>>, baselines: Arc<Mutex<HashMap<usize, u8>>>, debug: bool| {
+            let idx = match resolve_output_index(&outputs, item.pin, item.name.as_deref()) {
+                Some(idx) => idx,
+                None => return warp::reply::json(&PulseError { error: "invalid GPIO" }),
+            };
+            let entry = &outputs[idx];
+
+            // A new pulse on the same line supersedes any pulse already in flight.
+            // The baseline is captured once, when a chain starts, and reused for
+            // every request that supersedes it - re-reading the line's current
+            // value on a later request would just pick up the still-pulsed state.
+            let revert_value: u8 = {
+                let mut pulses_guard = pulses.lock().unwrap();
+                let mut baselines_guard = baselines.lock().unwrap();
+                let revert_value = if let Some(prev) = pulses_guard.remove(&idx) {
+                    prev.abort();
+                    *baselines_guard.get(&idx).unwrap_or(&0)
+                } else {
+                    let banks_guard = banks.lock().unwrap();
+                    let current = if get_bank_value(&banks_guard[entry.bank], entry.local) == "1" { 1 } else { 0 };
+                    baselines_guard.insert(idx, current);
+                    current
+                };
+                set_bank_value(&banks.lock().unwrap()[entry.bank], entry.local, item.state, debug);
+                revert_value
+            };
+
+            let banks_task = banks.clone();
+            let outputs_task = outputs.clone();
+            let pulses_task = pulses.clone();
+            let baselines_task = baselines.clone();
+            let duration_ms = item.duration_ms;
+            let join_handle = tokio::spawn(async move {
+                tokio::time::sleep(Duration::from_millis(duration_ms)).await;
+                let entry = &outputs_task[idx];
+                set_bank_value(&banks_task.lock().unwrap()[entry.bank], entry.local, revert_value, debug);
+                pulses_task.lock().unwrap().remove(&idx);
+                baselines_task.lock().unwrap().remove(&idx);
+            });
+            pulses.lock().unwrap().insert(idx, join_handle);
+
+            warp::reply::json(&PulseResponse {
+                pin: idx,
+                state: item.state,
+                revert_at_ms: now_ms() + item.duration_ms as u128,
+            })
+        });
+
+    let events = warp::path("events")
+        .and(warp::path::param::<String>())
+        .and(event_sources_filter.clone())
+        .and_then(|name: String, sources: Arc<Vec<EventSource>>| async move {
+            match sources.iter().find(|source| source.name == name) {
+                Some(source) => {
+                    let rx = source.sender.subscribe();
+                    Ok(warp::sse::reply(warp::sse::keep_alive().stream(event_stream(rx))))
+                }
+                None => Err(warp::reject::not_found()),
+            }
+        });
+
     let routes = get
         .or(set)
         .or(name_get)
         .or(name_set)
-        .or(gpio);
+        .or(set_batch)
+        .or(gpio)
+        .or(info)
+        .or(pulse)
+        .or(events);
 
     warp::serve(routes)
         .run(([0, 0, 0, 0], config.main.port))